@@ -91,11 +91,18 @@ use crate::data::{SyntaxNode, TimestampData};
 use crate::parser::Parser;
 use regex::Regex;
 use std::borrow::Cow;
+use std::collections::HashMap;
 
 const ORG_CLOSED_STRING: &str = "CLOSED";
 const ORG_DEADLINE_STRING: &str = "DEADLINE";
 const ORG_SCHEDULED_STRING: &str = "SCHEDULED";
 
+/// Default value of `org-archive-tag`.
+const ORG_ARCHIVE_TAG: &str = "ARCHIVE";
+
+/// Default value of `org-footnote-section`.
+const ORG_FOOTNOTE_SECTION: &str = "Footnotes";
+
 lazy_static! {
     pub static ref REGEX_HEADLINE_SHORT: Regex = Regex::new(r"^\*+\s").unwrap();
 
@@ -118,49 +125,166 @@ lazy_static! {
 
     pub static ref REGEX_CLOCK_LINE: Regex = Regex::new(r"(?i)^[ \t]*CLOCK:").unwrap();
 
-    /// Matches any of the TODO state keywords.
-    /// TODO parametrize
-    pub static ref REGEX_TODO: Regex = Regex::new(r"(?i)(TODO|DONE)[ \t]").unwrap();
+}
+
+/// A headline property whose computation is deferred until first access.
+///
+/// Modeled on org-element's split between the eager phase of
+/// `org-element-headline-parser` and `org-element--headline-deferred`:
+/// instead of eagerly building every field, the parser records only the
+/// byte offsets needed to resolve it later. The first call to a
+/// `HeadlineData` accessor resolves and memoizes the value; subsequent
+/// calls return the memoized result.
+pub enum Lazy<'a, T> {
+    Deferred(ResolveFn<'a, T>),
+    Resolved(T),
+}
+
+/// A thunk that resolves a deferred headline property against the
+/// original input slice.
+pub type ResolveFn<'a, T> = Box<dyn Fn(&'a str) -> T + 'a>;
+
+impl<'a, T: Clone> Lazy<'a, T> {
+    /// Resolve against `input` and memoize, or return the memoized value.
+    fn get(&mut self, input: &'a str) -> T {
+        if let Lazy::Deferred(resolve) = self {
+            *self = Lazy::Resolved(resolve(input));
+        }
+        match self {
+            Lazy::Resolved(value) => value.clone(),
+            Lazy::Deferred(_) => unreachable!("just resolved above"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn lazy_resolves_once_and_memoizes() {
+        let calls = Cell::new(0);
+        let mut lazy: Lazy<usize> = Lazy::Deferred(Box::new(|input: &str| {
+            calls.set(calls.get() + 1);
+            input.len()
+        }));
+        assert_eq!(lazy.get("hello"), 5);
+        assert_eq!(lazy.get("hello"), 5);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn todo_keyword_set_is_case_sensitive() {
+        let set = TodoKeywordSet::default();
+        assert!(set.is_done("DONE"));
+        assert!(!set.is_done("done"));
+        assert!(!set.is_done("TODO"));
+    }
+
+    #[test]
+    fn todo_keyword_set_honors_custom_done_keywords() {
+        let set = TodoKeywordSet::new(
+            vec![
+                "TODO".to_string(),
+                "WAITING".to_string(),
+                "DONE".to_string(),
+                "CANCELLED".to_string(),
+            ],
+            vec!["DONE".to_string(), "CANCELLED".to_string()],
+        );
+        assert!(set.is_done("CANCELLED"));
+        assert!(!set.is_done("WAITING"));
+    }
 
-    
-    /// TODO parametrize
-    /// check how org-done-keywords are set
-    pub static ref REGEX_TODO_DONE: Regex = Regex::new(r"(?i)DONE").unwrap();
+    #[test]
+    fn planning_line_regex_matches_each_keyword() {
+        for keyword in ["CLOSED", "DEADLINE", "SCHEDULED"] {
+            let line = format!("{}: <2024-01-01 Mon>", keyword);
+            let caps = REGEX_PLANNING_LINE
+                .captures(&line)
+                .unwrap_or_else(|| panic!("{} should match the planning line regex", keyword));
+            assert_eq!(&caps[1], format!("{}:", keyword));
+        }
+    }
 
+    #[test]
+    fn planning_line_regex_rejects_other_keywords() {
+        assert!(REGEX_PLANNING_LINE.captures("NOTE: not a planning keyword").is_none());
+    }
 
-    pub static ref REGEX_HEADLINE_PRIORITY: Regex = Regex::new(r"\[#.\][ \t]*").unwrap();
+    #[test]
+    fn parse_node_property_plain() {
+        let property = Parser::parse_node_property(":CUSTOM_ID: foo").unwrap();
+        assert_eq!(property.key(), "CUSTOM_ID");
+        assert_eq!(property.value(), "foo");
+        assert!(!property.appended);
+    }
 
+    #[test]
+    fn parse_node_property_appended() {
+        let property = Parser::parse_node_property(":KEY+: bar").unwrap();
+        assert_eq!(property.key(), "KEY");
+        assert_eq!(property.value(), "bar");
+        assert!(property.appended);
+    }
+
+    #[test]
+    fn parse_node_property_rejects_malformed_lines() {
+        assert!(Parser::parse_node_property("not a property").is_none());
+        assert!(Parser::parse_node_property(":: empty key").is_none());
+    }
 
+    #[test]
+    fn standard_properties_appends_plus_suffixed_values_in_order() {
+        let properties = vec![
+            Parser::parse_node_property(":KEY: foo").unwrap(),
+            Parser::parse_node_property(":KEY+: bar").unwrap(),
+        ];
+        let result = Parser::standard_properties(properties);
+        assert_eq!(result.get("KEY").unwrap(), "foo bar");
+    }
+
+    #[test]
+    fn standard_properties_overwrites_on_plain_repeat() {
+        let properties = vec![
+            Parser::parse_node_property(":KEY: foo").unwrap(),
+            Parser::parse_node_property(":KEY: bar").unwrap(),
+        ];
+        let result = Parser::standard_properties(properties);
+        assert_eq!(result.get("KEY").unwrap(), "bar");
+    }
 }
 
-pub struct HeadlineData<'a> {
+/// The fields a headline and an inline task have in common: TODO
+/// keyword, priority, tags, the archive/footnote flags derived from
+/// them, planning timestamps and the property-drawer map. Factored out
+/// so `HeadlineData` and `InlinetaskData` share one definition instead
+/// of duplicating this set.
+pub struct HeadlineComponents<'a> {
+    /// Input the headline was parsed from. Deferred fields are resolved
+    /// against this slice on first access.
+    input: &'a str,
+
+    /// Byte offsets of the raw title, stars/keyword/priority/tags
+    /// stripped. Used to resolve `title`, `footnote_section_p` lazily.
+    title_start: usize,
+    title_end: usize,
+
     /// Non_nil if the headline has an archive tag (boolean).
-    archivedp: bool,
+    archivedp: Lazy<'a, bool>,
 
     /// Headline's CLOSED reference, if any (timestamp object or nil)
     closed: Option<TimestampData<'a>>,
 
-    /// Non_nil if the headline has a comment keyword (boolean).
-    commentedp: bool,
-
     /// Headline's DEADLINE reference, if any (timestamp object or nil).
     deadline: Option<TimestampData<'a>>,
 
     /// Non_nil if the headline is a footnote section (boolean).
-    footnote_section_p: bool,
-
-    /// Reduced level of the headline (integer).
-    level: usize,
-
-    /// Number of blank lines between the headline
-    /// and the first non_blank line of its contents (integer).
-    pre_blank: usize,
+    footnote_section_p: Lazy<'a, bool>,
 
     /// Headline's priority, as a character (integer).
-    priority: Option<usize>,
-
-    /// Non_nil if the headline contains a quote keyword (boolean).
-    quotedp: bool,
+    priority: Lazy<'a, Option<usize>>,
 
     /// Raw headline's text, without the stars and the tags (string).
     raw_value: Cow<'a, str>,
@@ -170,16 +294,240 @@ pub struct HeadlineData<'a> {
 
     /// Headline's tags, if any, without
     /// the archive tag. (list of strings).
-    tags: Vec<Tag<'a>>,
+    tags: Lazy<'a, Vec<Tag<'a>>>,
 
     /// Parsed headline's text, without the stars
     /// and the tags (secondary string).
-    title: Option<Cow<'a, str>>,
+    title: Lazy<'a, Option<Cow<'a, str>>>,
 
     /// Headline's TODO keyword without quote and comment
     /// strings, if any (string or nil).
     /// also used instead of todo-type
-    todo_keyword: Option<TodoKeyword<'a>>,
+    todo_keyword: Lazy<'a, Option<TodoKeyword<'a>>>,
+
+    /// Properties set in the headline's property drawer, merged in with
+    /// upper-cased names (e.g. `CUSTOM_ID`, `ID`), matching how
+    /// org-element exposes them as `:CUSTOM_ID' etc. on the node.
+    properties: HashMap<String, Cow<'a, str>>,
+}
+
+impl<'a> HeadlineComponents<'a> {
+    pub fn todo_keyword(&mut self) -> Option<TodoKeyword<'a>> {
+        self.todo_keyword.get(self.input)
+    }
+
+    pub fn priority(&mut self) -> Option<usize> {
+        self.priority.get(self.input)
+    }
+
+    pub fn tags(&mut self) -> Vec<Tag<'a>> {
+        self.tags.get(self.input)
+    }
+
+    pub fn title(&mut self) -> Option<Cow<'a, str>> {
+        self.title.get(self.input)
+    }
+
+    pub fn archivedp(&mut self) -> bool {
+        self.archivedp.get(self.input)
+    }
+
+    pub fn footnote_section_p(&mut self) -> bool {
+        self.footnote_section_p.get(self.input)
+    }
+
+    pub fn raw_value(&self) -> &Cow<'a, str> {
+        &self.raw_value
+    }
+
+    pub fn closed(&self) -> Option<&TimestampData<'a>> {
+        self.closed.as_ref()
+    }
+
+    pub fn deadline(&self) -> Option<&TimestampData<'a>> {
+        self.deadline.as_ref()
+    }
+
+    pub fn scheduled(&self) -> Option<&TimestampData<'a>> {
+        self.scheduled.as_ref()
+    }
+
+    /// Looks up a standard property by name, case-insensitively (names
+    /// are stored upper-cased, e.g. `property("custom_id")` finds the
+    /// value set via `:CUSTOM_ID:` in the drawer).
+    pub fn property(&self, name: &str) -> Option<&Cow<'a, str>> {
+        self.properties.get(&name.to_uppercase())
+    }
+}
+
+pub struct HeadlineData<'a> {
+    /// Position of the first star (integer).
+    begin: usize,
+
+    /// Position of the end of the headline, stars included (integer).
+    end: usize,
+
+    /// Non_nil if the headline has a comment keyword (boolean).
+    commentedp: bool,
+
+    /// Reduced level of the headline (integer).
+    level: usize,
+
+    /// Number of blank lines between the headline
+    /// and the first non_blank line of its contents (integer).
+    pre_blank: usize,
+
+    /// Non_nil if the headline contains a quote keyword (boolean).
+    quotedp: bool,
+
+    /// Fields shared with `InlinetaskData`; see `HeadlineComponents`.
+    components: HeadlineComponents<'a>,
+}
+
+impl<'a> HeadlineData<'a> {
+    pub fn begin(&self) -> usize {
+        self.begin
+    }
+
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    pub fn todo_keyword(&mut self) -> Option<TodoKeyword<'a>> {
+        self.components.todo_keyword()
+    }
+
+    pub fn priority(&mut self) -> Option<usize> {
+        self.components.priority()
+    }
+
+    pub fn tags(&mut self) -> Vec<Tag<'a>> {
+        self.components.tags()
+    }
+
+    pub fn title(&mut self) -> Option<Cow<'a, str>> {
+        self.components.title()
+    }
+
+    pub fn archivedp(&mut self) -> bool {
+        self.components.archivedp()
+    }
+
+    pub fn footnote_section_p(&mut self) -> bool {
+        self.components.footnote_section_p()
+    }
+
+    pub fn property(&self, name: &str) -> Option<&Cow<'a, str>> {
+        self.components.property(name)
+    }
+}
+
+/// An inline task: a headline at or above `org-inlinetask-min-level`,
+/// terminated by an `END` line carrying the same number of stars rather
+/// than by a lower-or-equal-level headline. Shares every TODO/priority/
+/// tags/planning/property field with `HeadlineData` via
+/// `HeadlineComponents`.
+pub struct InlinetaskData<'a> {
+    /// Position of the first star (integer).
+    begin: usize,
+
+    /// Position right after the matching `END` line (integer).
+    end: usize,
+
+    /// Number of stars, at least `org-inlinetask-min-level` (integer).
+    level: usize,
+
+    /// Fields shared with `HeadlineData`; see `HeadlineComponents`.
+    components: HeadlineComponents<'a>,
+}
+
+impl<'a> InlinetaskData<'a> {
+    pub fn begin(&self) -> usize {
+        self.begin
+    }
+
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    pub fn todo_keyword(&mut self) -> Option<TodoKeyword<'a>> {
+        self.components.todo_keyword()
+    }
+
+    pub fn priority(&mut self) -> Option<usize> {
+        self.components.priority()
+    }
+
+    pub fn tags(&mut self) -> Vec<Tag<'a>> {
+        self.components.tags()
+    }
+
+    pub fn title(&mut self) -> Option<Cow<'a, str>> {
+        self.components.title()
+    }
+
+    pub fn archivedp(&mut self) -> bool {
+        self.components.archivedp()
+    }
+
+    pub fn footnote_section_p(&mut self) -> bool {
+        self.components.footnote_section_p()
+    }
+
+    pub fn property(&self, name: &str) -> Option<&Cow<'a, str>> {
+        self.components.property(name)
+    }
+}
+
+/// Mirrors `org-split-string` applied to the captured tag group, e.g.
+/// `:work:urgent:` -> `["work", "urgent"]`.
+fn parse_tags<'a>(tags: &'a str) -> Vec<Tag<'a>> {
+    tags.trim_matches(':')
+        .split(':')
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| Tag(Cow::from(tag)))
+        .collect()
+}
+
+/// Mirrors `org-element--headline-archivedp`: non-nil when the tag string
+/// contains `org-archive-tag`.
+fn resolve_archivedp(input: &str, tags_start: usize, tags_end: usize) -> bool {
+    parse_tags(&input[tags_start..tags_end])
+        .iter()
+        .any(|tag| tag.0 == ORG_ARCHIVE_TAG)
+}
+
+/// Mirrors `org-element--footnote-section-p`: non-nil when the trimmed
+/// raw title equals `org-footnote-section`.
+fn resolve_footnote_section_p(input: &str, title_start: usize, title_end: usize) -> bool {
+    input[title_start..title_end].trim() == ORG_FOOTNOTE_SECTION
+}
+
+/// Mirrors `org-element--headline-parse-title`. Trims the raw title text.
+///
+/// Object-level parsing (links, emphasis, etc.) is not implemented yet,
+/// so the secondary string degenerates to its raw form regardless of
+/// `raw_secondary_p`.
+fn parse_title<'a>(
+    input: &'a str,
+    title_start: usize,
+    title_end: usize,
+    _raw_secondary_p: bool,
+) -> Option<Cow<'a, str>> {
+    let trimmed = input[title_start..title_end].trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(Cow::from(trimmed))
+    }
 }
 
 // A planning is an element with the following pattern:
@@ -197,19 +545,111 @@ pub struct HeadlineData<'a> {
 pub struct NodePropertyData<'a> {
     key: Cow<'a, str>,
     value: Cow<'a, str>,
+
+    /// Whether this came from a `:KEY+:` line rather than a plain
+    /// `:KEY:` one — see `standard_properties`, which uses this to
+    /// decide whether to append to or overwrite an earlier value for
+    /// the same key.
+    appended: bool,
+}
+
+impl<'a> NodePropertyData<'a> {
+    pub fn key(&self) -> &Cow<'a, str> {
+        &self.key
+    }
+
+    pub fn value(&self) -> &Cow<'a, str> {
+        &self.value
+    }
+}
+
+/// A `:PROPERTIES:` ... `:END:` drawer, holding the node properties found
+/// between the two.
+pub struct PropertyDrawerData<'a> {
+    /// Position of the `:PROPERTIES:` line (integer).
+    begin: usize,
+
+    /// Position right after the `:END:` line (integer).
+    end: usize,
+
+    properties: Vec<NodePropertyData<'a>>,
 }
 
+impl<'a> PropertyDrawerData<'a> {
+    pub fn begin(&self) -> usize {
+        self.begin
+    }
+
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    pub fn properties(&self) -> &[NodePropertyData<'a>] {
+        &self.properties
+    }
+}
+
+#[derive(Clone)]
 pub struct Tag<'a>(Cow<'a, str>);
 
+#[derive(Clone)]
 pub struct TodoKeyword<'a>(Cow<'a, str>);
 
-
-// TODO this have to be defined by user set vaiable
 impl<'a> TodoKeyword<'a> {
-    fn is_done(&self) -> bool {
-        REGEX_TODO_DONE.find(&self.0).is_some()
+    /// True if this keyword belongs to the configured "done" keywords
+    /// (the `org-done-keywords` subset of `org-todo-keywords`), rather
+    /// than a regex match on the literal string "DONE" — keyword sets
+    /// are user-defined and case-sensitive.
+    pub fn is_done(&self, todo_keywords: &TodoKeywordSet) -> bool {
+        todo_keywords.is_done(&self.0)
+    }
+}
+
+/// The ordered set of active TODO keywords and the subset of them that
+/// count as "done", i.e. the `org-todo-keywords` / `org-done-keywords`
+/// distinction. Matching against this set must be case-sensitive: Org
+/// keywords are, by design, not case-folded.
+pub struct TodoKeywordSet {
+    /// All keywords in declaration order, e.g. `TODO`, `WAITING`, `DONE`,
+    /// `CANCELLED`.
+    keywords: Vec<String>,
+
+    /// The subset of `keywords` that mark a "done" state.
+    done_keywords: Vec<String>,
+}
+
+impl TodoKeywordSet {
+    pub fn new(keywords: Vec<String>, done_keywords: Vec<String>) -> Self {
+        TodoKeywordSet {
+            keywords,
+            done_keywords,
+        }
+    }
+
+    pub fn is_done(&self, keyword: &str) -> bool {
+        self.done_keywords.iter().any(|k| k == keyword)
     }
 
+    /// A case-sensitive alternation of the configured keywords, each
+    /// escaped so a keyword containing regexp metacharacters stays
+    /// literal.
+    fn alternation(&self) -> String {
+        self.keywords
+            .iter()
+            .map(|keyword| regex::escape(keyword))
+            .collect::<Vec<_>>()
+            .join("|")
+    }
+}
+
+impl Default for TodoKeywordSet {
+    /// The stock Org configuration: `(sequence "TODO" "DONE")`.
+    fn default() -> Self {
+        TodoKeywordSet::new(
+            vec!["TODO".to_string(), "DONE".to_string()],
+            vec!["DONE".to_string()],
+        )
+    }
 }
 
 pub enum TodoType {
@@ -217,7 +657,324 @@ pub enum TodoType {
     DONE,
 }
 
+/// Byte-offset spans captured by `Parser::parse_headline_line` in a
+/// single pass over a headline's line: stars (level), optional TODO
+/// keyword, optional priority cookie, title, and trailing tags group.
+/// Mirrors `org-complex-heading-regexp`.
+pub struct HeadlineLineMatch {
+    pub level: usize,
+    pub todo: Option<(usize, usize)>,
+    pub priority: Option<(usize, usize)>,
+    pub title: (usize, usize),
+    pub tags: Option<(usize, usize)>,
+    pub line_end: usize,
+}
+
 impl<'a> Parser<'a> {
+    /// Builds the equivalent of `org-complex-heading-regexp`: stars,
+    /// optional TODO keyword, optional priority cookie, title and
+    /// trailing tags captured in one expression, so a headline's line
+    /// only needs a single scan instead of one regexp pass per element.
+    /// Headline elements are separated by plain spaces, while leading
+    /// indentation before the stars may still mix tabs and spaces. Built
+    /// with no case-folding: keyword capture must stay case-sensitive.
+    ///
+    /// The stars may be followed by nothing at all — a bare `*` line is
+    /// valid Org syntax (see the module doc-comment's first example) —
+    /// so the separator between the stars and the keyword/priority/title
+    /// is only required when something follows; it isn't optional once
+    /// there's a title to separate it from.
+    ///
+    /// The compiled expression only depends on `todo_keywords`, which
+    /// rarely changes mid-parse, so it's cached in
+    /// `self.complex_heading_regex_cache` keyed by the keyword
+    /// alternation it was built from, instead of recompiling a regex on
+    /// every single headline line.
+    fn complex_heading_regex(&self) -> Regex {
+        let alternation = self.todo_keywords.alternation();
+
+        if let Some((cached_alternation, regex)) = &*self.complex_heading_regex_cache.borrow() {
+            if *cached_alternation == alternation {
+                return regex.clone();
+            }
+        }
+
+        let regex = Regex::new(&format!(
+            r"^[ \t]*(\*+)(?: +(?:({}) +)?(?:(\[#.\]) *)?(.*?))?(?: +(:[[:alnum:]_@#%:]+:))?[ \t]*$",
+            alternation
+        ))
+        .unwrap();
+
+        *self.complex_heading_regex_cache.borrow_mut() = Some((alternation, regex.clone()));
+        regex
+    }
+
+    /// Tokenizes the headline line starting at `pos` in a single regexp
+    /// pass via `complex_heading_regex`, returning the byte offsets of
+    /// each captured element (absolute, not relative to `pos`).
+    fn parse_headline_line(&self, pos: usize, limit: usize) -> Option<HeadlineLineMatch> {
+        let input = self.input;
+        let line_end = match input[pos..limit].find('\n') {
+            Some(offset) => pos + offset,
+            None => limit,
+        };
+        let caps = self.complex_heading_regex().captures(&input[pos..line_end])?;
+
+        let level = caps.get(1).unwrap().as_str().len();
+        let todo = caps.get(2).map(|m| (pos + m.start(), pos + m.end()));
+        let priority = caps.get(3).map(|m| (pos + m.start(), pos + m.end()));
+        let title = caps
+            .get(4)
+            .map(|m| (pos + m.start(), pos + m.end()))
+            .unwrap_or((line_end, line_end));
+        let tags = caps.get(5).map(|m| (pos + m.start(), pos + m.end()));
+
+        Some(HeadlineLineMatch {
+            level,
+            todo,
+            priority,
+            title,
+            tags,
+            line_end,
+        })
+    }
+
+    /// Builds the `HeadlineComponents` shared by `headline_parser` and
+    /// `inlinetask_parser` from an already-tokenized line: resolves the
+    /// planning line and property drawer that may follow it, and wraps
+    /// the deferred fields as `Lazy` thunks over `headline_match`'s
+    /// spans. Returns the components together with the position right
+    /// after the property drawer (or after the planning line, or after
+    /// the headline line itself, whichever is last present), for the
+    /// caller to resume scanning the body from.
+    fn build_headline_components(
+        &self,
+        headline_match: &HeadlineLineMatch,
+        raw_secondary_p: bool,
+        limit: usize,
+    ) -> (HeadlineComponents<'a>, usize) {
+        let input = self.input;
+        let (title_start, title_end) = headline_match.title;
+        let todo_span = headline_match.todo;
+        let priority_span = headline_match.priority;
+        let tags_span = headline_match.tags;
+        let raw_value = Cow::from(input[title_start..title_end].trim());
+
+        let (closed, deadline, scheduled, after_planning) =
+            self.time_properties(headline_match.line_end, limit);
+
+        // The property drawer, like the planning line, must immediately
+        // follow (no blank line) whatever precedes it.
+        let (properties, body_start) = if input.as_bytes().get(after_planning) == Some(&b'\n')
+            && after_planning + 1 < limit
+        {
+            match self.parse_property_drawer(after_planning + 1, limit) {
+                Some((properties, drawer_end)) => (Self::standard_properties(properties), drawer_end),
+                None => (HashMap::new(), after_planning),
+            }
+        } else {
+            (HashMap::new(), after_planning)
+        };
+
+        let components = HeadlineComponents {
+            input,
+            title_start,
+            title_end,
+            raw_value,
+            closed,
+            deadline,
+            scheduled,
+            properties,
+            todo_keyword: match todo_span {
+                None => Lazy::Resolved(None),
+                Some((start, end)) => Lazy::Deferred(Box::new(move |input: &'a str| {
+                    Some(TodoKeyword(Cow::from(&input[start..end])))
+                })),
+            },
+            priority: match priority_span {
+                None => Lazy::Resolved(None),
+                // `aref (match-string 0) 2`: the priority letter follows `[#`.
+                Some((start, _end)) => Lazy::Deferred(Box::new(move |input: &'a str| {
+                    input.as_bytes().get(start + 2).map(|&b| b as usize)
+                })),
+            },
+            tags: match tags_span {
+                None => Lazy::Resolved(Vec::new()),
+                Some((start, end)) => Lazy::Deferred(Box::new(move |input: &'a str| {
+                    parse_tags(&input[start..end])
+                })),
+            },
+            archivedp: match tags_span {
+                None => Lazy::Resolved(false),
+                Some((start, end)) => Lazy::Deferred(Box::new(move |input: &'a str| {
+                    resolve_archivedp(input, start, end)
+                })),
+            },
+            footnote_section_p: Lazy::Deferred(Box::new(move |input: &'a str| {
+                resolve_footnote_section_p(input, title_start, title_end)
+            })),
+            title: Lazy::Deferred(Box::new(move |input: &'a str| {
+                parse_title(input, title_start, title_end, raw_secondary_p)
+            })),
+        };
+
+        (components, body_start)
+    }
+
+    /// Parses the planning line immediately following a headline — no
+    /// blank line is allowed between the two — extracting its repeated
+    /// `KEYWORD: TIMESTAMP` pairs and returning them as
+    /// `(closed, deadline, scheduled, end)`, where `end` is the position
+    /// right after the planning line (or `headline_line_end` itself when
+    /// no planning line is present), for the caller to resume scanning
+    /// from. Mirrors `org-element--get-time-properties`.
+    ///
+    /// Exercising this end to end needs a `Parser` to call it on, which
+    /// in turn needs `Cursor`; neither is defined in this snapshot, so
+    /// the keyword-matching half of it (the part this file owns) is
+    /// covered directly via `REGEX_PLANNING_LINE` in the `tests` module
+    /// near the top of this file.
+    fn time_properties(
+        &self,
+        headline_line_end: usize,
+        limit: usize,
+    ) -> (
+        Option<TimestampData<'a>>,
+        Option<TimestampData<'a>>,
+        Option<TimestampData<'a>>,
+        usize,
+    ) {
+        let input = self.input;
+        if input.as_bytes().get(headline_line_end) != Some(&b'\n') {
+            return (None, None, None, headline_line_end);
+        }
+        let planning_start = headline_line_end + 1;
+        if planning_start >= limit {
+            return (None, None, None, headline_line_end);
+        }
+        let planning_end = match input[planning_start..limit].find('\n') {
+            Some(offset) => planning_start + offset,
+            None => limit,
+        };
+
+        let mut closed = None;
+        let mut deadline = None;
+        let mut scheduled = None;
+        let mut pos = planning_start;
+        let mut matched_any = false;
+
+        while pos < planning_end {
+            let keyword_match = match REGEX_PLANNING_LINE.captures(&input[pos..planning_end]) {
+                None => break,
+                Some(caps) => caps.get(1).unwrap(),
+            };
+            matched_any = true;
+            let keyword_end = pos + keyword_match.end();
+            let keyword = &input[pos + keyword_match.start()..keyword_end - 1];
+
+            let mut cursor = self.cursor.borrow_mut();
+            cursor.set(keyword_end);
+            cursor.skip_chars_forward(" \t", Some(planning_end));
+            drop(cursor);
+
+            let timestamp = self.timestamp_parser(planning_end);
+            match keyword {
+                ORG_CLOSED_STRING => closed = timestamp,
+                ORG_DEADLINE_STRING => deadline = timestamp,
+                ORG_SCHEDULED_STRING => scheduled = timestamp,
+                _ => {}
+            }
+
+            let next_pos = self.cursor.borrow().pos();
+            if next_pos <= keyword_end {
+                // No progress: a keyword with no parseable timestamp
+                // after it. Stop rather than loop forever.
+                break;
+            }
+            pos = next_pos;
+        }
+
+        if matched_any {
+            (closed, deadline, scheduled, planning_end)
+        } else {
+            (None, None, None, headline_line_end)
+        }
+    }
+
+    /// Parses a `:PROPERTIES:` ... `:END:` drawer starting at `pos`, one
+    /// `:KEY: value` pair per line, stopping at `:END:`. Returns the
+    /// parsed properties and the drawer's end position, or `None` if
+    /// `pos` isn't the start of a property drawer.
+    fn parse_property_drawer(
+        &self,
+        pos: usize,
+        limit: usize,
+    ) -> Option<(Vec<NodePropertyData<'a>>, usize)> {
+        let input = self.input;
+        let drawer_match = REGEX_PROPERTY_DRAWER.find(&input[pos..limit])?;
+        let drawer_end = pos + drawer_match.end();
+
+        let mut properties = Vec::new();
+        let mut lines = input[pos..drawer_end].lines();
+        lines.next(); // the `:PROPERTIES:` line itself
+        for line in lines {
+            let trimmed = line.trim();
+            if trimmed.eq_ignore_ascii_case(":END:") {
+                break;
+            }
+            if let Some(property) = Self::parse_node_property(trimmed) {
+                properties.push(property);
+            }
+        }
+        Some((properties, drawer_end))
+    }
+
+    /// Parses a single `:KEY: value` line, allowing an empty value and a
+    /// `+`-suffixed key for multi-value properties (e.g. `:KEY+: more`).
+    fn parse_node_property(line: &'a str) -> Option<NodePropertyData<'a>> {
+        let rest = line.strip_prefix(':')?;
+        let colon = rest.find(':')?;
+        let raw_key = &rest[..colon];
+        let appended = raw_key.ends_with('+');
+        let key = raw_key.strip_suffix('+').unwrap_or(raw_key);
+        if key.is_empty() {
+            return None;
+        }
+        let value = rest[colon + 1..].trim();
+        Some(NodePropertyData {
+            key: Cow::from(key),
+            value: Cow::from(value),
+            appended,
+        })
+    }
+
+    /// Upper-cases property keys, merging drawer properties into a
+    /// headline node the way org-element does (e.g. `:CUSTOM_ID').
+    ///
+    /// A `:KEY+:` line doesn't overwrite an earlier `:KEY:` value for
+    /// the same (upper-cased) key; it appends to it, separated by a
+    /// single space, in the order the lines appeared in the drawer —
+    /// mirroring how `org-entry-properties` folds `+`-suffixed
+    /// continuation lines instead of letting the last one win.
+    fn standard_properties(properties: Vec<NodePropertyData<'a>>) -> HashMap<String, Cow<'a, str>> {
+        let mut result: HashMap<String, Cow<'a, str>> = HashMap::new();
+        for property in properties {
+            let key = property.key.to_uppercase();
+            if property.appended {
+                if let Some(existing) = result.get_mut(&key) {
+                    let mut merged = existing.to_string();
+                    if !merged.is_empty() && !property.value.is_empty() {
+                        merged.push(' ');
+                    }
+                    merged.push_str(&property.value);
+                    *existing = Cow::from(merged);
+                    continue;
+                }
+            }
+            result.insert(key, property.value);
+        }
+        result
+    }
 
     /// Parse a headline.
     /// Return a list whose CAR is `headline' and CDR is a plist
@@ -237,45 +994,53 @@ impl<'a> Parser<'a> {
     /// When RAW-SECONDARY-P is non-nil, headline's title will not be
     /// parsed as a secondary string, but as a plain string instead.
     ///
-    /// Assume point is at beginning of the headline."
-
+    /// Assume point is at beginning of the headline.
+    ///
+    /// `:level', `:begin', `:end', the raw title and its bounds, the
+    /// planning line and the property drawer are computed eagerly here.
+    /// `:tags', `:priority', `:todo-keyword', `:archivedp',
+    /// `:footnote-section-p' and `:title' are stored as `Lazy' thunks
+    /// over the byte offsets found during this pass, and are only
+    /// resolved the first time a caller asks for them (see
+    /// `HeadlineData' accessors). The planning timestamps and the
+    /// drawer properties are not deferred.
+    ///
+    /// Those offsets come from a single `parse_headline_line` match
+    /// instead of the separate star-skip/TODO/priority/tags scans this
+    /// used to perform, each of which re-scanned the line from the
+    /// cursor.
     pub fn headline_parser(&self, limit: usize, raw_secondary_p: bool) -> SyntaxNode<'a> {
         let mut cursor = self.cursor.borrow_mut();
         let begin = cursor.pos();
 
-        let level = cursor.skip_chars_forward("*", Some(limit));
-        cursor.skip_chars_forward(" \t", Some(limit));
-
-        let todo = match cursor.capturing_at(&*REGEX_TODO) {
-            None => None,
-            Some(m) => {
-                let m0 = m.get(0).unwrap();
-                let m1 = m.get(1).unwrap();
-                cursor.set(m0.end());
-                cursor.skip_chars_forward(" \t", Some(limit));
-                Some(Cow::from(&self.input[m1.start()..m1.end()]))
-            }
-        };
-
-        // todo_type was moved into a method
+        // A single combined-regexp pass replaces the separate
+        // star-skipping, TODO, priority and tags scans that each used to
+        // re-scan the line from the cursor.
+        let headline_match = self
+            .parse_headline_line(begin, limit)
+            .expect("headline_parser assumes point is at the beginning of a headline");
+        let level = headline_match.level;
 
-        let priority = match cursor.looking_at(&*REGEX_HEADLINE_PRIORITY) {
-            None => None,
-            Some(m) => {
-                cursor.set(m.end());
-                //FIXME integer??
-                Some()
-            }
+        cursor.set(headline_match.line_end);
+        drop(cursor);
 
-        }
+        let (components, _body_start) =
+            self.build_headline_components(&headline_match, raw_secondary_p, limit);
 
-        // 	   (priority (and (looking-at "\\[#.\\][ \t]*")
-        // 			  (progn (goto-char (match-end 0))
-        // 				 (aref (match-string 0) 2))))
+        let mut cursor = self.cursor.borrow_mut();
 
+        let headline = HeadlineData {
+            begin,
+            end: limit, // FIXME requires org-end-of-subtree, not yet ported
+            pre_blank: 0, // FIXME requires contents-begin, not yet ported
+            commentedp: false, // FIXME requires org-comment-string match, not yet ported
+            quotedp: false, // FIXME mirrors commentedp, not yet ported
+            level,
+            components,
+        };
 
         cursor.set(begin);
-        unimplemented!()
+        SyntaxNode::Headline(headline)
         //   (save-excursion
         //     (let* ((begin (point))
         // 	   (level (prog1 (org-reduced-level (skip-chars-forward "*"))
@@ -359,18 +1124,118 @@ impl<'a> Parser<'a> {
         //
     }
 
-    // TODO implement inlinetask_parser
+    /// Finds the `END` line terminating an inline task of the given
+    /// `level`: the first line at or after `pos`, up to `limit`, with
+    /// exactly `level` stars followed (ignoring surrounding whitespace)
+    /// by the literal keyword `END`. Returns its `(start, end)` span, or
+    /// `None` if no such line exists before `limit`.
+    fn find_inlinetask_end(&self, level: usize, pos: usize, limit: usize) -> Option<(usize, usize)> {
+        let input = self.input;
+        let mut line_start = pos;
+        while line_start < limit {
+            let line_end = match input[line_start..limit].find('\n') {
+                Some(offset) => line_start + offset,
+                None => limit,
+            };
+            let line = input[line_start..line_end].trim_end();
+            let stars = line.chars().take_while(|&c| c == '*').count();
+            if stars == level && line[stars..].trim() == "END" {
+                return Some((line_start, line_end));
+            }
+            if line_end >= limit {
+                break;
+            }
+            line_start = line_end + 1;
+        }
+        None
+    }
+
+    /// Parse an inline task.
+    ///
+    /// Inline tasks are headlines at or above `inlinetask_min_level`,
+    /// terminated by an `END` line carrying the same number of stars,
+    /// rather than by the next same-or-lower-level headline. They reuse
+    /// the same deferred title/tags/priority/todo resolution, planning
+    /// line and property drawer parsing as `headline_parser`, via
+    /// `build_headline_components`.
+    ///
+    /// Assume point is at the beginning of the inline task.
     pub fn inlinetask_parser(&self, limit: usize, raw_secondary_p: bool) -> SyntaxNode<'a> {
-        unimplemented!()
+        let mut cursor = self.cursor.borrow_mut();
+        let begin = cursor.pos();
+
+        let headline_match = self
+            .parse_headline_line(begin, limit)
+            .expect("inlinetask_parser assumes point is at the beginning of an inline task");
+        let level = headline_match.level;
+        assert!(
+            level >= self.inlinetask_min_level,
+            "inlinetask_parser assumes point is at a headline of level >= inlinetask_min_level"
+        );
+
+        cursor.set(headline_match.line_end);
+        drop(cursor);
+
+        let (components, body_start) =
+            self.build_headline_components(&headline_match, raw_secondary_p, limit);
+
+        // FIXME: a missing END line is malformed input; org-element
+        // instead signals an error and lets the caller fall back to
+        // parsing this as a plain headline. Not yet ported.
+        let end = match self.find_inlinetask_end(level, body_start, limit) {
+            Some((_end_line_start, end_line_end)) => end_line_end,
+            None => limit,
+        };
+
+        let mut cursor = self.cursor.borrow_mut();
+
+        let inlinetask = InlinetaskData {
+            begin,
+            end,
+            level,
+            components,
+        };
+
+        cursor.set(begin);
+        SyntaxNode::Inlinetask(inlinetask)
     }
 
-    // TODO implement property_drawer_parser
+    /// Parse a property drawer.
+    ///
+    /// Assumes point is at the beginning of the drawer.
     pub fn property_drawer_parser(&self, limit: usize) -> SyntaxNode<'a> {
-        unimplemented!()
+        let mut cursor = self.cursor.borrow_mut();
+        let begin = cursor.pos();
+        let (properties, end) = match self.parse_property_drawer(begin, limit) {
+            Some((properties, drawer_end)) => (properties, drawer_end),
+            None => (Vec::new(), begin),
+        };
+        cursor.set(end);
+        drop(cursor);
+
+        SyntaxNode::PropertyDrawer(PropertyDrawerData {
+            begin,
+            end,
+            properties,
+        })
     }
 
-    // TODO implement node_property_parser
+    /// Parse a node property at point, i.e. a `:KEY: value` line inside a
+    /// property drawer.
+    ///
+    /// Assumes point is at the beginning of the property.
     pub fn node_property_parser(&self, limit: usize) -> SyntaxNode<'a> {
-        unimplemented!()
+        let mut cursor = self.cursor.borrow_mut();
+        let pos = cursor.pos();
+        let line_end = match self.input[pos..limit].find('\n') {
+            Some(offset) => pos + offset,
+            None => limit,
+        };
+        let property = Self::parse_node_property(self.input[pos..line_end].trim())
+            .expect("node_property_parser assumes point is at a `:KEY: value` line");
+        cursor.set(line_end);
+        drop(cursor);
+
+        SyntaxNode::NodeProperty(property)
     }
 }